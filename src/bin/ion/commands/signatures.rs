@@ -1,8 +1,11 @@
 use crate::commands::{CommandIo, IonCliCommand, WithIonCliArgument};
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use clap::{ArgMatches, Command};
 use ion_rs::*;
-use std::collections::HashMap;
+use roaring::RoaringBitmap;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
 
 pub struct SignaturesCommand;
 
@@ -32,20 +35,50 @@ impl IonCliCommand for SignaturesCommand {
                 .value_parser(clap::value_parser!(usize))
                 .default_value("2")
                 .help("Minimum size for signatures to be registered (default: 2)"))
+            .arg(clap::Arg::new("emit-schema")
+                .long("emit-schema")
+                .action(clap::ArgAction::SetTrue)
+                .help("Emit an Ion Schema Language document describing the detected signatures instead of a human-readable report"))
+            .arg(clap::Arg::new("similarity-threshold")
+                .long("similarity-threshold")
+                .value_parser(clap::value_parser!(f64))
+                .help("Cluster signatures whose estimated Jaccard similarity (via MinHash) meets this threshold (0.0-1.0), \
+                    merging them into a single signature with fields not shared by every member marked optional"))
+            .arg(clap::Arg::new("co-occurrence")
+                .long("co-occurrence")
+                .value_parser(clap::value_parser!(usize))
+                .value_name("SIGNATURE_ID")
+                .help("Report other signatures that share top-level documents with the given signature id, ranked by how heavily their occurrences overlap"))
+            .arg(clap::Arg::new("path")
+                .long("path")
+                .value_parser(clap::value_parser!(String))
+                .help("Restrict signature collection to values reached by this path selector, e.g. /customer/orders, /*, //, [type=struct]"))
     }
 
     fn run(&self, _command_path: &mut Vec<String>, args: &ArgMatches) -> Result<()> {
         let min_size = *args.get_one::<usize>("min-signature-size").unwrap();
+        let emit_schema = args.get_flag("emit-schema");
+        let similarity_threshold = args.get_one::<f64>("similarity-threshold").copied();
+        let co_occurrence_target = args.get_one::<usize>("co-occurrence").copied();
+        let path_steps = args.get_one::<String>("path")
+            .map(|expr| compile_path(expr))
+            .transpose()?;
         let mut signature_registry = SignatureRegistry::new();
-        
+        let mut doc_index: u32 = 0;
+
         CommandIo::new(args)?.for_each_input(|_output, input| {
             let mut reader = SystemReader::new(AnyEncoding, input.into_source());
-            
+
             loop {
                 match reader.next_item()? {
                     SystemStreamItem::EndOfStream(_) => break,
                     SystemStreamItem::Value(value) => {
-                        collect_signatures(value, &mut signature_registry, min_size, true)?;
+                        let (top_level, cursor) = match &path_steps {
+                            Some(steps) => (false, PathCursor::Active(steps)),
+                            None => (true, PathCursor::Unfiltered),
+                        };
+                        collect_signatures(value, &mut signature_registry, min_size, top_level, doc_index, cursor)?;
+                        doc_index += 1;
                     }
                     _ => continue,
                 }
@@ -55,12 +88,26 @@ impl IonCliCommand for SignaturesCommand {
 
         // Inline signatures with only one parent
         signature_registry.inline_single_parent_signatures();
-        
-        // Output results
-        let mut sorted_entries: Vec<_> = signature_registry.id_to_signature.iter().collect();
-        sorted_entries.sort_by(|a, b| b.1.count.cmp(&a.1.count));
-        for (id, entry) in sorted_entries {
-            println!("{} values appear with signature #{} {}", entry.count, id, entry.signature.display(&signature_registry));
+
+        if let Some(threshold) = similarity_threshold {
+            signature_registry.cluster_similar_signatures(threshold);
+        }
+
+        if let Some(target_id) = co_occurrence_target {
+            signature_registry.print_co_occurrence_report(target_id);
+        } else if emit_schema {
+            signature_registry.emit_isl_document();
+        } else {
+            // Output results. Under `--path`, only the signatures matched by the selector are
+            // "top level" for this run, so restrict the report to those — otherwise every
+            // signature in the surrounding envelope would still show up as noise.
+            let mut sorted_entries: Vec<_> = signature_registry.id_to_signature.iter()
+                .filter(|(_, entry)| path_steps.is_none() || entry.appears_top_level)
+                .collect();
+            sorted_entries.sort_by(|a, b| b.1.count.cmp(&a.1.count));
+            for (id, entry) in sorted_entries {
+                println!("{} values appear with signature #{} {}", entry.count, id, entry.signature.display(&signature_registry));
+            }
         }
 
         Ok(())
@@ -85,9 +132,19 @@ enum TypeSignature {
     Verbatim(ContainerSignature),
 }
 
+/// A single field of a `ContainerSignature::Struct`. `optional` is only ever set by
+/// MinHash-based clustering (see `SignatureRegistry::cluster_similar_signatures`), which marks
+/// a field optional when it wasn't present on every member of the cluster it merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct StructField {
+    name: String,
+    type_signature: TypeSignature,
+    optional: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum ContainerSignature {
-    Struct(Vec<(String, TypeSignature)>),
+    Struct(Vec<StructField>),
     List(Vec<TypeSignature>),
     SExp(Vec<TypeSignature>),
 }
@@ -97,7 +154,16 @@ struct SignatureRegistryEntry {
     signature: ContainerSignature,
     count: usize,
     parent_count: usize,
-    appears_top_level: bool
+    appears_top_level: bool,
+    /// The top-level document indices (assigned in `SignaturesCommand::run`) whose subtree
+    /// this signature appears in at least once. Pre-clustering, `count` is derivable as
+    /// `occurrences.len() + repeats_within_document`, kept separately so it doesn't require
+    /// recomputing on every lookup. `cluster_similar_signatures` sums `count` across merged
+    /// members while unioning their `occurrences`, so once two members share a document index
+    /// that identity no longer holds for the merged entry — `count` over-counts relative to
+    /// the deduplicated `occurrences`/`repeats_within_document` pair.
+    occurrences: RoaringBitmap,
+    repeats_within_document: usize,
 }
 
 struct SignatureRegistry {
@@ -114,52 +180,59 @@ impl SignatureRegistry {
             next_id: 0
         }
     }
-    
+
     // Returns (true, id) if entry is already present, (false, id) if it was not
-    fn get_or_create_id(&mut self, signature: ContainerSignature, top_level: bool) -> (bool, SignatureId) {
+    fn get_or_create_id(&mut self, signature: ContainerSignature, top_level: bool, doc_index: u32) -> (bool, SignatureId) {
         if let Some(&id) = self.signature_to_id.get(&signature) {
             let entry = self.id_to_signature.get_mut(&id).unwrap();
             entry.count += 1;
             entry.appears_top_level |= top_level;
+            if !entry.occurrences.insert(doc_index) {
+                entry.repeats_within_document += 1;
+            }
             (true, id)
         } else {
             let id = self.next_id;
             self.next_id += 1;
+            let mut occurrences = RoaringBitmap::new();
+            occurrences.insert(doc_index);
             self.id_to_signature.insert(id, SignatureRegistryEntry {
                 signature: signature.clone(),
                 count: 1,
                 parent_count: 0,
-                appears_top_level: top_level
+                appears_top_level: top_level,
+                occurrences,
+                repeats_within_document: 0,
             });
             self.signature_to_id.insert(signature, id);
             (false, id)
         }
     }
-    
+
     fn inline_single_parent_signatures(&mut self) {
         let single_parent_ids: Vec<SignatureId> = self.id_to_signature.iter()
             .filter(|(_, entry)| entry.parent_count == 1 && ! entry.appears_top_level)
             .map(|(&id, _)| id)
             .collect();
-        
+
         for id in single_parent_ids {
             let signature_to_inline = self.id_to_signature[&id].signature.clone();
-            
+
             for entry in self.id_to_signature.values_mut() {
                 Self::replace_container_refs(&mut entry.signature, id, &signature_to_inline);
             }
-            
+
             self.id_to_signature.remove(&id);
         }
     }
-    
+
     fn replace_container_refs(sig: &mut ContainerSignature, target_id: SignatureId, replacement: &ContainerSignature) {
         match sig {
             ContainerSignature::Struct(fields) => {
-                for (_, typ) in fields {
-                    if let TypeSignature::Container(id) = typ {
+                for field in fields {
+                    if let TypeSignature::Container(id) = &field.type_signature {
                         if *id == target_id {
-                            *typ = TypeSignature::Verbatim(replacement.clone());
+                            field.type_signature = TypeSignature::Verbatim(replacement.clone());
                         }
                     }
                 }
@@ -175,13 +248,394 @@ impl SignatureRegistry {
             }
         }
     }
+
+    /// Repoints any `TypeSignature::Container(old_id)` references at `new_id` instead. Used by
+    /// `cluster_similar_signatures` when two or more signatures are merged into one, so that
+    /// anything that referenced one of the merged-away ids now references the survivor.
+    fn repoint_container_refs(sig: &mut ContainerSignature, old_id: SignatureId, new_id: SignatureId) {
+        match sig {
+            ContainerSignature::Struct(fields) => {
+                for field in fields {
+                    if let TypeSignature::Container(id) = &mut field.type_signature {
+                        if *id == old_id {
+                            *id = new_id;
+                        }
+                    }
+                }
+            }
+            ContainerSignature::List(elements) | ContainerSignature::SExp(elements) => {
+                for typ in elements {
+                    if let TypeSignature::Container(id) = typ {
+                        if *id == old_id {
+                            *id = new_id;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Groups structurally-similar signatures using MinHash-estimated Jaccard similarity and
+    /// merges each group into a single signature. Two registered signatures are merged when
+    /// their estimated similarity meets `threshold`; merging a group of struct signatures marks
+    /// any field not shared by every member as optional (`field?: type`) rather than discarding
+    /// the divergence, so schema drift across near-duplicate shapes stays visible.
+    fn cluster_similar_signatures(&mut self, threshold: f64) {
+        let seeds: Vec<u64> = (0..MINHASH_SEED_COUNT).collect();
+
+        let mut ids: Vec<SignatureId> = self.id_to_signature.keys().copied().collect();
+        ids.sort_unstable();
+
+        let sketches: Vec<Vec<u64>> = ids.iter()
+            .map(|id| minhash_sketch(&self.id_to_signature[id].signature.shingles(self), &seeds))
+            .collect();
+
+        let mut union_find = UnionFind::new(ids.len());
+        for i in 0..ids.len() {
+            for j in (i + 1)..ids.len() {
+                if !same_kind(&self.id_to_signature[&ids[i]].signature, &self.id_to_signature[&ids[j]].signature) {
+                    continue;
+                }
+                let agreeing = sketches[i].iter().zip(&sketches[j]).filter(|(a, b)| a == b).count();
+                let estimated_jaccard = agreeing as f64 / seeds.len() as f64;
+                if estimated_jaccard >= threshold {
+                    union_find.union(i, j);
+                }
+            }
+        }
+
+        let mut clusters: HashMap<usize, Vec<SignatureId>> = HashMap::new();
+        for (i, &id) in ids.iter().enumerate() {
+            clusters.entry(union_find.find(i)).or_default().push(id);
+        }
+
+        for members in clusters.into_values() {
+            if members.len() < 2 {
+                continue;
+            }
+
+            let survivor_id = *members.iter().min().unwrap();
+            let member_signatures: Vec<ContainerSignature> = members.iter()
+                .map(|id| self.id_to_signature[id].signature.clone())
+                .collect();
+            let mut merged_signature = merge_container_signatures(&member_signatures);
+            let merged_count: usize = members.iter().map(|id| self.id_to_signature[id].count).sum();
+            let merged_parent_count: usize = members.iter().map(|id| self.id_to_signature[id].parent_count).sum();
+            let appears_top_level = members.iter().any(|id| self.id_to_signature[id].appears_top_level);
+            let merged_repeats: usize = members.iter().map(|id| self.id_to_signature[id].repeats_within_document).sum();
+            let merged_occurrences: RoaringBitmap = members.iter()
+                .map(|id| self.id_to_signature[id].occurrences.clone())
+                .fold(RoaringBitmap::new(), |acc, bitmap| acc | bitmap);
+
+            for &id in &members {
+                if id != survivor_id {
+                    self.id_to_signature.remove(&id);
+                }
+            }
+            self.signature_to_id.retain(|_, id| !members.contains(id) || *id == survivor_id);
+
+            for &old_id in &members {
+                if old_id != survivor_id {
+                    for entry in self.id_to_signature.values_mut() {
+                        Self::repoint_container_refs(&mut entry.signature, old_id, survivor_id);
+                    }
+                    // The merged signature itself may reference a member that got folded away
+                    // (e.g. a struct field whose type was one of the other cluster members), so
+                    // it needs the same repointing before it becomes the survivor's signature.
+                    Self::repoint_container_refs(&mut merged_signature, old_id, survivor_id);
+                }
+            }
+
+            let pre_merge_signature = self.id_to_signature[&survivor_id].signature.clone();
+            self.signature_to_id.remove(&pre_merge_signature);
+            self.signature_to_id.insert(merged_signature.clone(), survivor_id);
+            let survivor = self.id_to_signature.get_mut(&survivor_id).unwrap();
+            survivor.signature = merged_signature;
+            survivor.count = merged_count;
+            survivor.parent_count = merged_parent_count;
+            survivor.appears_top_level = appears_top_level;
+            survivor.repeats_within_document = merged_repeats;
+            survivor.occurrences = merged_occurrences;
+        }
+    }
+
+    /// Prints every signature whose occurrence set shares at least one document with
+    /// `target_id`'s, ranked from heaviest to lightest overlap by the Jaccard similarity of
+    /// their `RoaringBitmap` document-index sets. Reveals which
+    /// container shapes tend to appear together within the same top-level records.
+    fn print_co_occurrence_report(&self, target_id: SignatureId) {
+        let Some(target) = self.id_to_signature.get(&target_id) else {
+            println!("No signature registered with id #{}", target_id);
+            return;
+        };
+
+        let mut overlaps: Vec<(SignatureId, u64, f64)> = self.id_to_signature.iter()
+            .filter(|(&id, _)| id != target_id)
+            .filter_map(|(&id, entry)| {
+                let intersection = (&target.occurrences & &entry.occurrences).len();
+                if intersection == 0 {
+                    return None;
+                }
+                let union = (&target.occurrences | &entry.occurrences).len();
+                Some((id, intersection, intersection as f64 / union as f64))
+            })
+            .collect();
+        overlaps.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+
+        println!("Signatures co-occurring with #{} {}", target_id, target.signature.display(self));
+        for (id, intersection, jaccard) in overlaps {
+            let entry = &self.id_to_signature[&id];
+            println!("  #{} {} (overlap {:.2}, {} shared documents)", id, entry.signature.display(self), jaccard, intersection);
+        }
+    }
+
+    /// Emits the registered signatures as a standalone Ion Schema Language document. Every
+    /// retained signature becomes a named `type::` definition; signatures that were observed
+    /// at the top level of the input are called out as the document's root types so a reader
+    /// knows which definitions to validate whole values against.
+    fn emit_isl_document(&self) {
+        let mut sorted_entries: Vec<_> = self.id_to_signature.iter().collect();
+        sorted_entries.sort_by_key(|(&id, _)| id);
+
+        let root_type_names: Vec<String> = sorted_entries.iter()
+            .filter(|(_, entry)| entry.appears_top_level)
+            .map(|(&id, _)| isl_type_name(id))
+            .collect();
+        if !root_type_names.is_empty() {
+            println!("// Root types: {}", root_type_names.join(", "));
+            println!();
+        }
+
+        for (&id, entry) in sorted_entries {
+            println!("type::{{");
+            println!("  name: {},", isl_type_name(id));
+            println!("  {}", entry.signature.isl_constraints(self).replace('\n', "\n  "));
+            println!("}}");
+            println!();
+        }
+    }
+}
+
+fn isl_type_name(id: SignatureId) -> String {
+    format!("sig_{}", id)
+}
+
+/// Renders a field name as an ISL symbol, quoting it when it isn't a valid unquoted Ion
+/// identifier (empty, starting with a digit, or containing anything other than ASCII
+/// letters/digits/underscore/dollar) so the emitted document stays re-ingestable by ion-schema
+/// tooling instead of producing a parse error on names like `first name`.
+fn isl_quote_symbol(name: &str) -> String {
+    let is_plain_identifier = !name.is_empty()
+        && name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_plain_identifier {
+        name.to_string()
+    } else {
+        let escaped = name.replace('\\', "\\\\").replace('\'', "\\'");
+        format!("'{}'", escaped)
+    }
+}
+
+const MINHASH_SEED_COUNT: u64 = 64;
+
+fn hash_feature_with_seed(feature: &str, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    feature.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Computes an N-length MinHash sketch for a feature set: for each seed, the minimum hash over
+/// all features. The fraction of sketch positions at which two sketches agree estimates the
+/// Jaccard similarity of the underlying feature sets.
+fn minhash_sketch(features: &HashSet<String>, seeds: &[u64]) -> Vec<u64> {
+    seeds.iter()
+        .map(|&seed| features.iter().map(|f| hash_feature_with_seed(f, seed)).min().unwrap_or(u64::MAX))
+        .collect()
+}
+
+fn same_kind(a: &ContainerSignature, b: &ContainerSignature) -> bool {
+    matches!(
+        (a, b),
+        (ContainerSignature::Struct(_), ContainerSignature::Struct(_))
+            | (ContainerSignature::List(_), ContainerSignature::List(_))
+            | (ContainerSignature::SExp(_), ContainerSignature::SExp(_))
+    )
+}
+
+/// Merges a cluster of same-kind signatures into one. Struct fields are unioned by name; a field
+/// missing from some members is kept but marked optional. List/sexp elements are merged
+/// positionally, using at each index the type of the first member that has an element there.
+fn merge_container_signatures(signatures: &[ContainerSignature]) -> ContainerSignature {
+    match &signatures[0] {
+        ContainerSignature::Struct(_) => {
+            let mut field_names: Vec<String> = Vec::new();
+            for sig in signatures {
+                if let ContainerSignature::Struct(fields) = sig {
+                    for field in fields {
+                        if !field_names.contains(&field.name) {
+                            field_names.push(field.name.clone());
+                        }
+                    }
+                }
+            }
+            field_names.sort();
+
+            let merged_fields = field_names.into_iter().map(|name| {
+                let mut type_signature = None;
+                let mut present_count = 0;
+                for sig in signatures {
+                    if let ContainerSignature::Struct(fields) = sig {
+                        if let Some(field) = fields.iter().find(|f| f.name == name) {
+                            present_count += 1;
+                            if type_signature.is_none() {
+                                type_signature = Some(field.type_signature.clone());
+                            }
+                        }
+                    }
+                }
+                StructField {
+                    name,
+                    type_signature: type_signature.unwrap(),
+                    optional: present_count < signatures.len(),
+                }
+            }).collect();
+
+            ContainerSignature::Struct(merged_fields)
+        }
+        ContainerSignature::List(_) => ContainerSignature::List(merge_sequence_elements(signatures, |sig| {
+            if let ContainerSignature::List(elements) = sig { Some(elements) } else { None }
+        })),
+        ContainerSignature::SExp(_) => ContainerSignature::SExp(merge_sequence_elements(signatures, |sig| {
+            if let ContainerSignature::SExp(elements) = sig { Some(elements) } else { None }
+        })),
+    }
+}
+
+fn merge_sequence_elements(
+    signatures: &[ContainerSignature],
+    as_elements: impl Fn(&ContainerSignature) -> Option<&Vec<TypeSignature>>,
+) -> Vec<TypeSignature> {
+    let max_len = signatures.iter()
+        .filter_map(|sig| as_elements(sig).map(Vec::len))
+        .max()
+        .unwrap_or(0);
+
+    (0..max_len).map(|index| {
+        signatures.iter()
+            .find_map(|sig| as_elements(sig).and_then(|elements| elements.get(index)).cloned())
+            .unwrap()
+    }).collect()
+}
+
+/// Classic union-find (disjoint-set) with path compression, used to group signatures whose
+/// pairwise estimated similarity clears the clustering threshold.
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        Self { parent: (0..size).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        if root_a != root_b {
+            self.parent[root_a] = root_b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod clustering_tests {
+    use super::*;
+
+    fn struct_sig(fields: &[(&str, TypeSignature)]) -> ContainerSignature {
+        ContainerSignature::Struct(
+            fields.iter()
+                .map(|(name, typ)| StructField { name: name.to_string(), type_signature: typ.clone(), optional: false })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn minhash_sketch_is_deterministic_and_seed_length_sized() {
+        let seeds: Vec<u64> = (0..8).collect();
+        let features: HashSet<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let first = minhash_sketch(&features, &seeds);
+        let second = minhash_sketch(&features, &seeds);
+        assert_eq!(first.len(), seeds.len());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn minhash_sketch_differs_for_disjoint_feature_sets() {
+        let seeds: Vec<u64> = (0..32).collect();
+        let a: HashSet<String> = ["a:int", "b:int"].iter().map(|s| s.to_string()).collect();
+        let b: HashSet<String> = ["x:string", "y:string"].iter().map(|s| s.to_string()).collect();
+        assert_ne!(minhash_sketch(&a, &seeds), minhash_sketch(&b, &seeds));
+    }
+
+    #[test]
+    fn same_kind_compares_container_variant_not_contents() {
+        let a = ContainerSignature::Struct(vec![]);
+        let b = struct_sig(&[("x", TypeSignature::Int)]);
+        let c = ContainerSignature::List(vec![]);
+        assert!(same_kind(&a, &b));
+        assert!(!same_kind(&a, &c));
+    }
+
+    #[test]
+    fn union_find_merges_transitively_connected_members() {
+        let mut uf = UnionFind::new(4);
+        uf.union(0, 1);
+        uf.union(1, 2);
+        assert_eq!(uf.find(0), uf.find(2));
+        assert_ne!(uf.find(0), uf.find(3));
+    }
+
+    #[test]
+    fn merge_container_signatures_unions_struct_fields_and_marks_partial_ones_optional() {
+        let a = struct_sig(&[("id", TypeSignature::Int), ("name", TypeSignature::String)]);
+        let b = struct_sig(&[("id", TypeSignature::Int)]);
+
+        let merged = merge_container_signatures(&[a, b]);
+        let ContainerSignature::Struct(fields) = merged else { panic!("expected a struct") };
+
+        let id_field = fields.iter().find(|f| f.name == "id").unwrap();
+        assert!(!id_field.optional);
+
+        let name_field = fields.iter().find(|f| f.name == "name").unwrap();
+        assert!(name_field.optional);
+        assert_eq!(name_field.type_signature, TypeSignature::String);
+    }
+
+    #[test]
+    fn merge_sequence_elements_merges_positionally_using_the_first_member_present() {
+        let a = ContainerSignature::List(vec![TypeSignature::Int, TypeSignature::String]);
+        let b = ContainerSignature::List(vec![TypeSignature::Int]);
+
+        let merged = merge_container_signatures(&[a, b]);
+        let ContainerSignature::List(elements) = merged else { panic!("expected a list") };
+
+        assert_eq!(elements, vec![TypeSignature::Int, TypeSignature::String]);
+    }
 }
 
 impl ContainerSignature {
     fn size(&self, registry: &SignatureRegistry) -> usize {
         match self {
             ContainerSignature::Struct(fields) => {
-                fields.len() + fields.iter().map(|(_, typ)| typ.container_size(registry)).sum::<usize>()
+                fields.len() + fields.iter().map(|f| f.type_signature.container_size(registry)).sum::<usize>()
             }
             ContainerSignature::List(elements) => {
                 elements.len() + elements.iter().map(|typ| typ.container_size(registry)).sum::<usize>()
@@ -191,12 +645,13 @@ impl ContainerSignature {
             }
         }
     }
-    
+
     fn display(&self, registry: &SignatureRegistry) -> String {
         match self {
             ContainerSignature::Struct(fields) => {
-                let field_strs: Vec<String> = fields.iter().map(|(name, typ)| {
-                    format!("{}: {}", name, typ.display(registry))
+                let field_strs: Vec<String> = fields.iter().map(|field| {
+                    let optional_marker = if field.optional { "?" } else { "" };
+                    format!("{}{}: {}", field.name, optional_marker, field.type_signature.display(registry))
                 }).collect();
                 format!("{{ {} }}", field_strs.join(", "))
             }
@@ -210,6 +665,54 @@ impl ContainerSignature {
             }
         }
     }
+
+    /// Renders the constraints (`type`, `fields`/`ordered_elements`) that go inside an ISL
+    /// type definition for this signature. Used both for named top-level definitions and for
+    /// inline anonymous types (containers that fell under `min-signature-size` or were inlined
+    /// into their single parent).
+    fn isl_constraints(&self, registry: &SignatureRegistry) -> String {
+        match self {
+            ContainerSignature::Struct(fields) => {
+                let field_strs: Vec<String> = fields.iter()
+                    .map(|field| {
+                        let name = isl_quote_symbol(&field.name);
+                        if field.optional {
+                            match &field.type_signature {
+                                TypeSignature::Verbatim(sig) => format!("{}: {{ {}, occurs: optional }}", name, sig.isl_constraints(registry)),
+                                other => format!("{}: {{ type: {}, occurs: optional }}", name, other.isl_type_name(registry)),
+                            }
+                        } else {
+                            format!("{}: {}", name, field.type_signature.isl_type_ref(registry))
+                        }
+                    })
+                    .collect();
+                format!("type: struct,\n  fields: {{\n    {}\n  }}", field_strs.join(",\n    "))
+            }
+            ContainerSignature::List(elements) => {
+                let elem_strs: Vec<String> = elements.iter().map(|typ| typ.isl_type_ref(registry)).collect();
+                format!("type: list,\n  ordered_elements: [\n    {}\n  ]", elem_strs.join(",\n    "))
+            }
+            ContainerSignature::SExp(elements) => {
+                let elem_strs: Vec<String> = elements.iter().map(|typ| typ.isl_type_ref(registry)).collect();
+                format!("type: sexp,\n  ordered_elements: [\n    {}\n  ]", elem_strs.join(",\n    "))
+            }
+        }
+    }
+
+    /// A feature set of shingles describing this container's shape, used as input to MinHash
+    /// sketching: `(field name, type tag)` pairs for structs, `(position, type tag)` pairs for
+    /// lists/sexps.
+    fn shingles(&self, registry: &SignatureRegistry) -> HashSet<String> {
+        match self {
+            ContainerSignature::Struct(fields) => fields.iter()
+                .map(|field| format!("{}:{}", field.name, field.type_signature.type_tag(registry)))
+                .collect(),
+            ContainerSignature::List(elements) | ContainerSignature::SExp(elements) => elements.iter()
+                .enumerate()
+                .map(|(index, typ)| format!("{}:{}", index, typ.type_tag(registry)))
+                .collect(),
+        }
+    }
 }
 
 impl TypeSignature {
@@ -220,7 +723,7 @@ impl TypeSignature {
             _ => 0,
         }
     }
-    
+
     fn display(&self, registry: &SignatureRegistry) -> String {
         match self {
             TypeSignature::Null => "null".to_string(),
@@ -237,6 +740,251 @@ impl TypeSignature {
             TypeSignature::Verbatim(sig) => sig.display(registry),
         }
     }
+
+    /// A short tag describing this type's shape, coarse enough that two containers with the
+    /// same field/position layout shingle the same way regardless of nested detail: scalar
+    /// variants map to their own tag, containers (whether already registered or still
+    /// `Verbatim`) map to `struct`/`list`/`sexp`.
+    fn type_tag(&self, registry: &SignatureRegistry) -> &'static str {
+        match self {
+            TypeSignature::Null => "null",
+            TypeSignature::Bool => "bool",
+            TypeSignature::Int => "int",
+            TypeSignature::Float => "float",
+            TypeSignature::Decimal => "decimal",
+            TypeSignature::Timestamp => "timestamp",
+            TypeSignature::String => "string",
+            TypeSignature::Symbol => "symbol",
+            TypeSignature::Blob => "blob",
+            TypeSignature::Clob => "clob",
+            TypeSignature::Container(id) => container_kind_tag(&registry.id_to_signature[id].signature),
+            TypeSignature::Verbatim(sig) => container_kind_tag(sig),
+        }
+    }
+
+    /// Renders this type as an ISL type reference suitable for use as the value of a `fields`
+    /// entry or an `ordered_elements` entry: a named reference for registered containers
+    /// (`{ type: sig_3 }` — a bare name, since `$`-prefixed identifiers denote nullable core
+    /// types in ISL rather than references to user-defined types), a core type name for
+    /// scalars, or an inline anonymous type for containers without their own definition (too
+    /// small to get one, or inlined into their single parent).
+    fn isl_type_ref(&self, registry: &SignatureRegistry) -> String {
+        match self {
+            TypeSignature::Null => "{ type: $null }".to_string(),
+            TypeSignature::Bool => "{ type: bool }".to_string(),
+            TypeSignature::Int => "{ type: int }".to_string(),
+            TypeSignature::Float => "{ type: float }".to_string(),
+            TypeSignature::Decimal => "{ type: decimal }".to_string(),
+            TypeSignature::Timestamp => "{ type: timestamp }".to_string(),
+            TypeSignature::String => "{ type: string }".to_string(),
+            TypeSignature::Symbol => "{ type: symbol }".to_string(),
+            TypeSignature::Blob => "{ type: blob }".to_string(),
+            TypeSignature::Clob => "{ type: clob }".to_string(),
+            TypeSignature::Container(id) => format!("{{ type: {} }}", isl_type_name(*id)),
+            TypeSignature::Verbatim(sig) => format!("{{ {} }}", sig.isl_constraints(registry)),
+        }
+    }
+
+    /// The bare ISL type name this type refers to (used for the `type:` key of a field that
+    /// also carries an `occurs: optional` constraint, where `isl_type_ref`'s wrapping braces
+    /// would be redundant).
+    fn isl_type_name(&self, registry: &SignatureRegistry) -> String {
+        match self {
+            TypeSignature::Null => "$null".to_string(),
+            TypeSignature::Bool => "bool".to_string(),
+            TypeSignature::Int => "int".to_string(),
+            TypeSignature::Float => "float".to_string(),
+            TypeSignature::Decimal => "decimal".to_string(),
+            TypeSignature::Timestamp => "timestamp".to_string(),
+            TypeSignature::String => "string".to_string(),
+            TypeSignature::Symbol => "symbol".to_string(),
+            TypeSignature::Blob => "blob".to_string(),
+            TypeSignature::Clob => "clob".to_string(),
+            TypeSignature::Container(id) => isl_type_name(*id),
+            TypeSignature::Verbatim(sig) => match sig {
+                ContainerSignature::Struct(_) => "struct".to_string(),
+                ContainerSignature::List(_) => "list".to_string(),
+                ContainerSignature::SExp(_) => "sexp".to_string(),
+            },
+        }
+    }
+}
+
+fn container_kind_tag(sig: &ContainerSignature) -> &'static str {
+    match sig {
+        ContainerSignature::Struct(_) => "struct",
+        ContainerSignature::List(_) => "list",
+        ContainerSignature::SExp(_) => "sexp",
+    }
+}
+
+/// A single step of a compiled `--path` selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathStep {
+    /// `/name` — a struct field step.
+    Field(String),
+    /// `/0` — a list/sexp index step.
+    Index(usize),
+    /// `/*` — any single field or element.
+    Wildcard,
+    /// `//` — zero or more levels, matched lazily against the steps that follow it.
+    RecursiveDescent,
+    /// `[type=struct]` — filters the value arrived at by its core Ion type.
+    TypePredicate(String),
+}
+
+/// Compiles a `--path` selector expression (e.g. `/customer/orders`, `/*`, `//[type=struct]`)
+/// into a sequence of `PathStep`s, modeled on the Preserves path query language. The expression
+/// must start with `/`; a doubled `/` denotes recursive descent.
+fn compile_path(expr: &str) -> Result<Vec<PathStep>> {
+    if !expr.starts_with('/') {
+        bail!("path selector must start with '/': {expr}");
+    }
+    let mut steps = Vec::new();
+    let mut segments = expr.split('/').peekable();
+    segments.next(); // drop the empty segment before the leading '/'
+    while let Some(segment) = segments.next() {
+        if segment.is_empty() {
+            // An empty segment between two slashes is `//`; a lone trailing one just ends the path.
+            if segments.peek().is_some() {
+                steps.push(PathStep::RecursiveDescent);
+            }
+        } else {
+            steps.push(parse_path_segment(segment)?);
+        }
+    }
+    Ok(steps)
+}
+
+fn parse_path_segment(segment: &str) -> Result<PathStep> {
+    if let Some(inner) = segment.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+        let (key, value) = inner.split_once('=')
+            .ok_or_else(|| anyhow!("malformed path predicate: [{inner}]"))?;
+        if key.trim() != "type" {
+            bail!("unsupported path predicate: [{inner}]");
+        }
+        return Ok(PathStep::TypePredicate(value.trim().to_string()));
+    }
+    if segment == "*" {
+        return Ok(PathStep::Wildcard);
+    }
+    if let Ok(index) = segment.parse::<usize>() {
+        return Ok(PathStep::Index(index));
+    }
+    Ok(PathStep::Field(segment.to_string()))
+}
+
+/// Tracks how much of a compiled `--path` selector has been satisfied while walking the Ion
+/// tree. `Unfiltered` reproduces the pre-`--path` behavior (only the document root is
+/// top-level). `Active` carries the steps still owed as we descend into fields/elements.
+/// `Done` means the path either matched already (its match point registers as top-level, and
+/// everything below collects normally) or failed to match this branch.
+#[derive(Debug, Clone, Copy)]
+enum PathCursor<'a> {
+    Unfiltered,
+    Active(&'a [PathStep]),
+    Done,
+}
+
+/// Consumes a move step (`Field`/`Index`/`Wildcard`/`RecursiveDescent`) against the field name
+/// or list/sexp index being descended into, returning the steps remaining after the move, or
+/// `None` if this branch doesn't match. `RecursiveDescent` first tries matching the step after
+/// it here; if that fails, it stays pending so deeper descendants can still match it.
+fn advance_steps<'a>(steps: &'a [PathStep], field: Option<&str>, index: Option<usize>) -> Option<&'a [PathStep]> {
+    match steps.first()? {
+        PathStep::Field(name) => (field == Some(name.as_str())).then(|| &steps[1..]),
+        PathStep::Wildcard => Some(&steps[1..]),
+        PathStep::Index(i) => (index == Some(*i)).then(|| &steps[1..]),
+        PathStep::RecursiveDescent => advance_steps(&steps[1..], field, index).or(Some(steps)),
+        PathStep::TypePredicate(_) => None,
+    }
+}
+
+fn advance_path<'a>(cursor: PathCursor<'a>, field: Option<&str>, index: Option<usize>) -> PathCursor<'a> {
+    match cursor {
+        PathCursor::Unfiltered => PathCursor::Unfiltered,
+        PathCursor::Done => PathCursor::Done,
+        PathCursor::Active(steps) => match advance_steps(steps, field, index) {
+            Some(rest) => PathCursor::Active(rest),
+            None => PathCursor::Done,
+        },
+    }
+}
+
+/// Whether the remaining steps are satisfied by the value just arrived at: empty steps are an
+/// immediate match, and a leading `TypePredicate` is checked against `kind` (the value's core
+/// Ion type tag) before being consumed. A leading `RecursiveDescent` can also match zero levels
+/// here — e.g. a trailing `//`, or `//[type=struct]` once the predicate passes — so it's worth
+/// trying to resolve at this node before falling back to "keep searching deeper".
+fn resolve_match<'a>(steps: &'a [PathStep], kind: &str) -> Option<&'a [PathStep]> {
+    match steps.first() {
+        None => Some(steps),
+        Some(PathStep::TypePredicate(expected)) => {
+            (expected == kind).then(|| resolve_match(&steps[1..], kind)).flatten()
+        }
+        Some(PathStep::RecursiveDescent) => {
+            let rest = &steps[1..];
+            if rest.is_empty() {
+                // A trailing `//` matches immediately, having descended zero levels.
+                return Some(rest);
+            }
+            match resolve_match(rest, kind) {
+                // The predicate(s) right after `//` were satisfied here (or resolved to a
+                // full match) — commit to that, dropping the now-spent `//`.
+                Some(remaining) if remaining.len() < rest.len() => Some(remaining),
+                // Either a predicate rejected this node, or `rest` starts with a real move
+                // step that can only be taken by descending — keep the whole `//` pending
+                // so deeper nodes get a fresh chance to match it.
+                _ => Some(steps),
+            }
+        }
+        Some(_) => Some(steps),
+    }
+}
+
+/// Given the cursor in effect when arriving at a value, determines whether that value should
+/// be registered as top-level and the cursor its children should see.
+///
+/// A full match under a leading `RecursiveDescent` stays pending for the children rather than
+/// transitioning to `Done`: `//` means "this value and any matching value nested inside it", so
+/// a struct reached via `//[type=struct]` must still let a nested struct match again, the same
+/// way XPath's `//` keeps searching below a hit instead of stopping at the shallowest one.
+fn resolve_top_level<'a>(cursor: PathCursor<'a>, fallback_top_level: bool, kind: &str) -> (bool, PathCursor<'a>) {
+    match cursor {
+        PathCursor::Unfiltered => (fallback_top_level, PathCursor::Unfiltered),
+        PathCursor::Done => (false, PathCursor::Done),
+        PathCursor::Active(steps) => match resolve_match(steps, kind) {
+            None => (false, PathCursor::Done),
+            Some(remaining) if remaining.is_empty() => {
+                let child_cursor = if matches!(steps.first(), Some(PathStep::RecursiveDescent)) {
+                    PathCursor::Active(steps)
+                } else {
+                    PathCursor::Done
+                };
+                (true, child_cursor)
+            }
+            Some(remaining) => (false, PathCursor::Active(remaining)),
+        },
+    }
+}
+
+fn value_kind_tag(value: &ValueRef<AnyEncoding>) -> &'static str {
+    use ValueRef::*;
+    match value {
+        Null(_) => "null",
+        Bool(_) => "bool",
+        Int(_) => "int",
+        Float(_) => "float",
+        Decimal(_) => "decimal",
+        Timestamp(_) => "timestamp",
+        String(_) => "string",
+        Symbol(_) => "symbol",
+        Blob(_) => "blob",
+        Clob(_) => "clob",
+        Struct(_) => "struct",
+        List(_) => "list",
+        SExp(_) => "sexp",
+    }
 }
 
 fn collect_sequence_signatures<'a, I>(
@@ -244,19 +992,22 @@ fn collect_sequence_signatures<'a, I>(
     registry: &mut SignatureRegistry,
     min_size: usize,
     top_level: bool,
+    doc_index: u32,
+    cursor: PathCursor,
     signature_constructor: impl FnOnce(Vec<TypeSignature>) -> ContainerSignature,
 ) -> Result<TypeSignature>
 where
     I: Iterator<Item = Result<LazyValue<'a, AnyEncoding>, IonError>>,
 {
     let mut elements = Vec::new();
-    for element in elements_iter {
-        let element_type = collect_signatures(element?, registry, min_size, false)?;
+    for (index, element) in elements_iter.enumerate() {
+        let element_cursor = advance_path(cursor, None, Some(index));
+        let element_type = collect_signatures(element?, registry, min_size, false, doc_index, element_cursor)?;
         elements.push(element_type);
     }
     let container_sig = signature_constructor(elements.clone());
     if container_sig.size(registry) >= min_size {
-        let (existing, id) = registry.get_or_create_id(container_sig, top_level);
+        let (existing, id) = registry.get_or_create_id(container_sig, top_level, doc_index);
         if !existing {
             for typ in &elements {
                 if let TypeSignature::Container(child_id) = typ {
@@ -270,9 +1021,11 @@ where
     }
 }
 
-fn collect_signatures(value: LazyValue<AnyEncoding>, registry: &mut SignatureRegistry, min_size: usize, top_level: bool) -> Result<TypeSignature> {
+fn collect_signatures(value: LazyValue<AnyEncoding>, registry: &mut SignatureRegistry, min_size: usize, top_level: bool, doc_index: u32, cursor: PathCursor) -> Result<TypeSignature> {
     use ValueRef::*;
-    Ok(match value.read()? {
+    let value_ref = value.read()?;
+    let (top_level, child_cursor) = resolve_top_level(cursor, top_level, value_kind_tag(&value_ref));
+    Ok(match value_ref {
         Null(_) => TypeSignature::Null,
         Bool(_) => TypeSignature::Bool,
         Int(_) => TypeSignature::Int,
@@ -288,16 +1041,17 @@ fn collect_signatures(value: LazyValue<AnyEncoding>, registry: &mut SignatureReg
             for field in s {
                 let field = field?;
                 let field_name = field.name()?.text().unwrap_or("").to_string();
-                let field_type = collect_signatures(field.value(), registry, min_size, false)?;
-                fields.push((field_name, field_type));
+                let field_cursor = advance_path(child_cursor, Some(&field_name), None);
+                let field_type = collect_signatures(field.value(), registry, min_size, false, doc_index, field_cursor)?;
+                fields.push(StructField { name: field_name, type_signature: field_type, optional: false });
             }
-            fields.sort_by(|a, b| a.0.cmp(&b.0));
+            fields.sort_by(|a, b| a.name.cmp(&b.name));
             let container_sig = ContainerSignature::Struct(fields.clone());
             if container_sig.size(registry) >= min_size {
-                let (existing, id) = registry.get_or_create_id(container_sig, top_level);
+                let (existing, id) = registry.get_or_create_id(container_sig, top_level, doc_index);
                 if ! existing {
-                    for (_, typ) in &fields {
-                        if let TypeSignature::Container(child_id) = typ {
+                    for field in &fields {
+                        if let TypeSignature::Container(child_id) = &field.type_signature {
                             registry.id_to_signature.get_mut(child_id).unwrap().parent_count += 1;
                         }
                     }
@@ -312,6 +1066,8 @@ fn collect_signatures(value: LazyValue<AnyEncoding>, registry: &mut SignatureReg
             registry,
             min_size,
             top_level,
+            doc_index,
+            child_cursor,
             ContainerSignature::List,
         )?,
         SExp(s) => collect_sequence_signatures(
@@ -319,7 +1075,112 @@ fn collect_signatures(value: LazyValue<AnyEncoding>, registry: &mut SignatureReg
             registry,
             min_size,
             top_level,
+            doc_index,
+            child_cursor,
             ContainerSignature::SExp,
         )?,
     })
 }
+
+#[cfg(test)]
+mod path_selector_tests {
+    use super::*;
+
+    #[test]
+    fn compile_path_rejects_missing_leading_slash() {
+        assert!(compile_path("customer/orders").is_err());
+    }
+
+    #[test]
+    fn compile_path_parses_fields_indices_wildcards_and_predicates() {
+        assert_eq!(
+            compile_path("/customer/orders/0/*").unwrap(),
+            vec![
+                PathStep::Field("customer".to_string()),
+                PathStep::Field("orders".to_string()),
+                PathStep::Index(0),
+                PathStep::Wildcard,
+            ]
+        );
+        assert_eq!(
+            compile_path("//[type=struct]").unwrap(),
+            vec![PathStep::RecursiveDescent, PathStep::TypePredicate("struct".to_string())]
+        );
+    }
+
+    #[test]
+    fn compile_path_rejects_malformed_or_unsupported_predicates() {
+        assert!(compile_path("/[kind=struct]").is_err());
+        assert!(compile_path("/[type]").is_err());
+    }
+
+    #[test]
+    fn advance_steps_matches_field_and_index_moves() {
+        let steps = vec![PathStep::Field("orders".to_string()), PathStep::Index(0)];
+        let after_field = advance_steps(&steps, Some("orders"), None).unwrap();
+        assert_eq!(after_field, &steps[1..]);
+        assert!(advance_steps(&steps, Some("other"), None).is_none());
+        assert_eq!(advance_steps(after_field, None, Some(0)).unwrap(), &steps[2..]);
+        assert!(advance_steps(after_field, None, Some(1)).is_none());
+    }
+
+    #[test]
+    fn advance_steps_wildcard_matches_any_move() {
+        let steps = vec![PathStep::Wildcard];
+        assert_eq!(advance_steps(&steps, Some("anything"), None).unwrap(), &steps[1..]);
+        assert_eq!(advance_steps(&steps, None, Some(7)).unwrap(), &steps[1..]);
+    }
+
+    #[test]
+    fn advance_steps_recursive_descent_stays_pending_until_the_next_step_matches() {
+        let steps = vec![PathStep::RecursiveDescent, PathStep::Field("target".to_string())];
+        // A move that doesn't match "target" leaves the `//` pending for deeper descendants.
+        assert_eq!(advance_steps(&steps, Some("other"), None).unwrap(), &steps[..]);
+        // A move that matches "target" consumes both the `//` and the field step.
+        assert_eq!(advance_steps(&steps, Some("target"), None).unwrap(), &steps[2..]);
+    }
+
+    #[test]
+    fn resolve_match_handles_empty_steps_and_type_predicates() {
+        assert_eq!(resolve_match(&[], "struct"), Some(&[][..]));
+
+        let matching = vec![PathStep::TypePredicate("struct".to_string())];
+        assert_eq!(resolve_match(&matching, "struct"), Some(&[][..]));
+
+        let mismatching = vec![PathStep::TypePredicate("struct".to_string())];
+        assert_eq!(resolve_match(&mismatching, "list"), None);
+    }
+
+    #[test]
+    fn resolve_match_trailing_recursive_descent_matches_immediately() {
+        let steps = vec![PathStep::RecursiveDescent];
+        assert_eq!(resolve_match(&steps, "struct"), Some(&[][..]));
+    }
+
+    #[test]
+    fn resolve_match_recursive_descent_resolves_a_following_predicate() {
+        let steps = vec![PathStep::RecursiveDescent, PathStep::TypePredicate("struct".to_string())];
+        assert_eq!(resolve_match(&steps, "struct"), Some(&[][..]));
+        // A non-matching node leaves the whole `//[type=...]` pending for deeper nodes.
+        assert_eq!(resolve_match(&steps, "list"), Some(&steps[..]));
+    }
+
+    #[test]
+    fn resolve_top_level_keeps_recursive_descent_pending_on_full_match() {
+        let steps = vec![PathStep::RecursiveDescent, PathStep::TypePredicate("struct".to_string())];
+        let (is_top_level, child_cursor) = resolve_top_level(PathCursor::Active(&steps), false, "struct");
+        assert!(is_top_level);
+        match child_cursor {
+            PathCursor::Active(remaining) => assert_eq!(remaining, &steps[..]),
+            other => panic!("expected the `//` to stay pending for nested matches, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn resolve_top_level_closes_a_plain_match_to_done() {
+        let steps = vec![PathStep::Field("orders".to_string())];
+        let (is_top_level, child_cursor) = resolve_top_level(PathCursor::Active(&steps[1..]), false, "list");
+        assert!(is_top_level);
+        assert!(matches!(child_cursor, PathCursor::Done));
+    }
+}